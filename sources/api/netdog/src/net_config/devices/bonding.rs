@@ -5,6 +5,7 @@ use crate::net_config::devices::generate_addressing_validation;
 use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 use snafu::ensure;
+use std::collections::HashSet;
 use std::net::IpAddr;
 
 #[derive(Debug, Deserialize)]
@@ -20,10 +21,13 @@ pub(crate) struct NetBondV1 {
     pub(crate) routes: Option<Vec<RouteV1>>,
     kind: String,
     pub(crate) mode: BondMode,
+    #[serde(rename = "options")]
+    pub(crate) options: Option<BondOptions>,
     #[serde(rename = "min-links")]
     pub(crate) min_links: Option<usize>,
     #[serde(rename = "monitoring")]
     pub(crate) monitoring_config: BondMonitoringConfig,
+    pub(crate) mtu: Option<Mtu>,
     pub(crate) interfaces: Vec<InterfaceName>,
 }
 
@@ -73,15 +77,248 @@ impl Validate for NetBondV1 {
             BondMonitoringConfig::ArpMon(config) => config.validate()?,
         }
 
+        ensure!(
+            self.primary.is_none() || self.mode.supports_primary(),
+            error::InvalidNetConfigSnafu {
+                reason: "primary is only applicable to active-backup, balance-tlb, and balance-alb bonds"
+            }
+        );
+        if self.min_links.is_some() {
+            ensure!(
+                self.mode.supports_min_links(),
+                error::InvalidNetConfigSnafu {
+                    reason: "min-links is only applicable to 802.3ad bonds"
+                }
+            )
+        }
+
+        // Validate mode-specific options
+        let options = self.options.clone().unwrap_or_default();
+        ensure!(
+            options.transmit_hash_policy.is_none() || self.mode.supports_transmit_hash_policy(),
+            error::InvalidNetConfigSnafu {
+                reason: "transmit-hash-policy is only applicable to balance-xor, 802.3ad, balance-tlb, and balance-alb bonds"
+            }
+        );
+        ensure!(
+            options.lacp_rate.is_none() && options.ad_select.is_none() || self.mode.is_8023ad(),
+            error::InvalidNetConfigSnafu {
+                reason: "lacp-rate and ad-select are only applicable to 802.3ad bonds"
+            }
+        );
+
+        if let Some(Mtu::Value(mtu)) = &self.mtu {
+            ensure!(
+                *mtu >= IPV6_MIN_MTU,
+                error::InvalidNetConfigSnafu {
+                    reason: "mtu must be 'auto' or at least 1280, the IPv6 minimum"
+                }
+            );
+        }
+
         Ok(())
     }
 }
 
-// Currently only mode 1 (active-backup) is supported but eventually 0-6 could be added
-#[derive(Clone, Debug, Deserialize)]
+impl NetBondV1 {
+    /// Render this bond's `.netdev` unit, the file systemd-networkd reads to create the bond
+    /// device and configure its mode, mode-specific options, and link monitoring.
+    pub(crate) fn to_netdev_config(&self, name: &InterfaceName) -> String {
+        let mut netdev = format!("[NetDev]\nName={}\nKind=bond\n", name);
+        if let Some(Mtu::Value(mtu)) = &self.mtu {
+            netdev.push_str(&format!("MTUBytes={}\n", mtu));
+        }
+
+        netdev.push_str("\n[Bond]\n");
+        netdev.push_str(&format!("Mode={}\n", self.mode.as_systemd_str()));
+        if let Some(min_links) = self.min_links {
+            netdev.push_str(&format!("MinLinks={}\n", min_links));
+        }
+        if let Some(options) = &self.options {
+            if let Some(policy) = &options.transmit_hash_policy {
+                netdev.push_str(&format!("TransmitHashPolicy={}\n", policy.as_systemd_str()));
+            }
+            if let Some(rate) = &options.lacp_rate {
+                netdev.push_str(&format!("LACPTransmitRate={}\n", rate.as_systemd_str()));
+            }
+            if let Some(ad_select) = &options.ad_select {
+                netdev.push_str(&format!("AdSelect={}\n", ad_select.as_systemd_str()));
+            }
+        }
+        match &self.monitoring_config {
+            BondMonitoringConfig::MiiMon(config) => netdev.push_str(&config.to_netdev_config()),
+            BondMonitoringConfig::ArpMon(config) => netdev.push_str(&config.to_netdev_config()),
+        }
+
+        netdev
+    }
+}
+
+/// The smallest MTU IPv6 requires a link to support.
+const IPV6_MIN_MTU: u16 = 1280;
+
+/// A device's MTU, either pinned to an explicit value or left to the backend's default via
+/// `auto`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Mtu {
+    Auto,
+    Value(u16),
+}
+
+impl<'de> Deserialize<'de> for Mtu {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum MtuRepr {
+            Auto(String),
+            Value(u16),
+        }
+
+        match MtuRepr::deserialize(deserializer)? {
+            MtuRepr::Auto(s) if s.eq_ignore_ascii_case("auto") => Ok(Mtu::Auto),
+            MtuRepr::Auto(s) => Err(D::Error::custom(format!(
+                "invalid mtu '{}', expected an integer or 'auto'",
+                s
+            ))),
+            MtuRepr::Value(mtu) => Ok(Mtu::Value(mtu)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum BondMode {
+    BalanceRr,
     ActiveBackup,
+    BalanceXor,
+    Broadcast,
+    #[serde(rename = "802.3ad")]
+    Ieee8023ad,
+    BalanceTlb,
+    BalanceAlb,
+}
+
+impl BondMode {
+    /// Whether this mode uses a transmit-hash policy to select the outgoing slave.
+    fn supports_transmit_hash_policy(&self) -> bool {
+        matches!(
+            self,
+            BondMode::BalanceXor
+                | BondMode::Ieee8023ad
+                | BondMode::BalanceTlb
+                | BondMode::BalanceAlb
+        )
+    }
+
+    /// Whether this mode is 802.3ad (LACP), the only mode that accepts an LACP rate,
+    /// aggregation-selection policy, or min-links.
+    fn is_8023ad(&self) -> bool {
+        matches!(self, BondMode::Ieee8023ad)
+    }
+
+    fn supports_min_links(&self) -> bool {
+        self.is_8023ad()
+    }
+
+    /// Whether this mode honors the `primary` slave setting.
+    fn supports_primary(&self) -> bool {
+        matches!(
+            self,
+            BondMode::ActiveBackup | BondMode::BalanceTlb | BondMode::BalanceAlb
+        )
+    }
+
+    /// The value systemd-networkd's `.netdev` `Mode=` setting expects for this mode.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            BondMode::BalanceRr => "balance-rr",
+            BondMode::ActiveBackup => "active-backup",
+            BondMode::BalanceXor => "balance-xor",
+            BondMode::Broadcast => "broadcast",
+            BondMode::Ieee8023ad => "802.3ad",
+            BondMode::BalanceTlb => "balance-tlb",
+            BondMode::BalanceAlb => "balance-alb",
+        }
+    }
+}
+
+/// Mode-specific bonding knobs that only apply to certain `BondMode`s; `NetBondV1::validate`
+/// enforces that each is only set when the configured mode actually uses it.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct BondOptions {
+    #[serde(rename = "transmit-hash-policy")]
+    pub(crate) transmit_hash_policy: Option<BondTransmitHashPolicy>,
+    #[serde(rename = "lacp-rate")]
+    pub(crate) lacp_rate: Option<LacpRate>,
+    #[serde(rename = "ad-select")]
+    pub(crate) ad_select: Option<AdSelect>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum BondTransmitHashPolicy {
+    Layer2,
+    #[serde(rename = "layer2+3")]
+    Layer23,
+    #[serde(rename = "layer3+4")]
+    Layer34,
+    #[serde(rename = "encap2+3")]
+    Encap23,
+    #[serde(rename = "encap3+4")]
+    Encap34,
+}
+
+impl BondTransmitHashPolicy {
+    /// The value systemd-networkd's `TransmitHashPolicy=` setting expects for this policy.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            BondTransmitHashPolicy::Layer2 => "layer2",
+            BondTransmitHashPolicy::Layer23 => "layer2+3",
+            BondTransmitHashPolicy::Layer34 => "layer3+4",
+            BondTransmitHashPolicy::Encap23 => "encap2+3",
+            BondTransmitHashPolicy::Encap34 => "encap3+4",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum LacpRate {
+    Slow,
+    Fast,
+}
+
+impl LacpRate {
+    /// The value systemd-networkd's `LACPTransmitRate=` setting expects for this rate.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            LacpRate::Slow => "slow",
+            LacpRate::Fast => "fast",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum AdSelect {
+    Stable,
+    Bandwidth,
+    Count,
+}
+
+impl AdSelect {
+    /// The value systemd-networkd's `AdSelect=` setting expects for this policy.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            AdSelect::Stable => "stable",
+            AdSelect::Bandwidth => "bandwidth",
+            AdSelect::Count => "count",
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -122,6 +359,16 @@ impl Validate for MiiMonitoringConfig {
     }
 }
 
+impl MiiMonitoringConfig {
+    /// Render this monitor's settings as lines of a `.netdev` `[Bond]` section.
+    fn to_netdev_config(&self) -> String {
+        format!(
+            "MIIMonitorSec={}ms\nUpDelaySec={}ms\nDownDelaySec={}ms\n",
+            self.frequency, self.updelay, self.downdelay
+        )
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct ArpMonitoringConfig {
@@ -131,6 +378,8 @@ pub(crate) struct ArpMonitoringConfig {
     pub(crate) validate: ArpValidate,
     #[serde(rename = "arpmon-targets")]
     pub(crate) targets: Vec<IpAddr>,
+    #[serde(rename = "arpmon-all-targets")]
+    pub(crate) all_targets: Option<ArpAllTargets>,
 }
 
 impl Validate for ArpMonitoringConfig {
@@ -149,10 +398,51 @@ impl Validate for ArpMonitoringConfig {
                 reason: "arpmon-targets must include between 1 and 16 targets"
             }
         );
+
+        let unique_targets: HashSet<&IpAddr> = self.targets.iter().collect();
+        ensure!(
+            unique_targets.len() == self.targets.len(),
+            error::InvalidNetConfigSnafu {
+                reason: "arpmon-targets must not contain duplicate addresses"
+            }
+        );
+
+        // The kernel's classic ARP monitor is IPv4-only. This only forbids mixing families within
+        // one monitor; a target list made up entirely of IPv6 addresses is accepted.
+        ensure!(
+            self.targets.iter().all(IpAddr::is_ipv4) || self.targets.iter().all(IpAddr::is_ipv6),
+            error::InvalidNetConfigSnafu {
+                reason: "arpmon-targets must not mix IPv4 and IPv6 addresses"
+            }
+        );
+
         Ok(())
     }
 }
 
+impl ArpMonitoringConfig {
+    /// Render this monitor's settings as lines of a `.netdev` `[Bond]` section.
+    fn to_netdev_config(&self) -> String {
+        let targets = self
+            .targets
+            .iter()
+            .map(IpAddr::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut config = format!(
+            "ARPIntervalSec={}ms\nARPIPTargets={}\nARPValidate={}\n",
+            self.interval,
+            targets,
+            self.validate.as_systemd_str()
+        );
+        if let Some(all_targets) = &self.all_targets {
+            config.push_str(&format!("ARPAllTargets={}\n", all_targets.as_systemd_str()));
+        }
+
+        config
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub(crate) enum ArpValidate {
@@ -161,3 +451,262 @@ pub(crate) enum ArpValidate {
     Backup,
     None,
 }
+
+impl ArpValidate {
+    /// The value systemd-networkd's `ARPValidate=` setting expects for this mode.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            ArpValidate::Active => "active",
+            ArpValidate::All => "all",
+            ArpValidate::Backup => "backup",
+            ArpValidate::None => "none",
+        }
+    }
+}
+
+/// Whether a bond slave is considered up when any configured ARP target responds, or only when
+/// all of them do. Defaults to `any`, matching the kernel's default.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub(crate) enum ArpAllTargets {
+    Any,
+    All,
+}
+
+impl ArpAllTargets {
+    /// The value systemd-networkd's `ARPAllTargets=` setting expects for this mode.
+    fn as_systemd_str(&self) -> &'static str {
+        match self {
+            ArpAllTargets::Any => "any",
+            ArpAllTargets::All => "all",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn mii_monitoring() -> BondMonitoringConfig {
+        BondMonitoringConfig::MiiMon(MiiMonitoringConfig {
+            frequency: 100,
+            updelay: 200,
+            downdelay: 200,
+        })
+    }
+
+    fn bond(mode: BondMode) -> NetBondV1 {
+        NetBondV1 {
+            primary: None,
+            dhcp4: None,
+            dhcp6: None,
+            static4: None,
+            static6: None,
+            routes: None,
+            kind: "bond".to_string(),
+            mode,
+            options: None,
+            min_links: None,
+            monitoring_config: mii_monitoring(),
+            mtu: None,
+            interfaces: vec![InterfaceName::try_from("eth0").unwrap()],
+        }
+    }
+
+    #[test]
+    fn primary_allowed_for_active_backup() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.primary = Some(true);
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn primary_rejected_for_unsupported_mode() {
+        let mut b = bond(BondMode::BalanceRr);
+        b.primary = Some(true);
+        assert!(b.validate().is_err());
+    }
+
+    #[test]
+    fn min_links_rejected_outside_8023ad() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.min_links = Some(1);
+        b.interfaces = vec![
+            InterfaceName::try_from("eth0").unwrap(),
+            InterfaceName::try_from("eth1").unwrap(),
+        ];
+        assert!(b.validate().is_err());
+    }
+
+    #[test]
+    fn min_links_allowed_for_8023ad() {
+        let mut b = bond(BondMode::Ieee8023ad);
+        b.min_links = Some(1);
+        b.interfaces = vec![
+            InterfaceName::try_from("eth0").unwrap(),
+            InterfaceName::try_from("eth1").unwrap(),
+        ];
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn transmit_hash_policy_rejected_for_active_backup() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.options = Some(BondOptions {
+            transmit_hash_policy: Some(BondTransmitHashPolicy::Layer2),
+            lacp_rate: None,
+            ad_select: None,
+        });
+        assert!(b.validate().is_err());
+    }
+
+    #[test]
+    fn transmit_hash_policy_allowed_for_balance_xor() {
+        let mut b = bond(BondMode::BalanceXor);
+        b.options = Some(BondOptions {
+            transmit_hash_policy: Some(BondTransmitHashPolicy::Layer23),
+            lacp_rate: None,
+            ad_select: None,
+        });
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn lacp_rate_and_ad_select_rejected_outside_8023ad() {
+        let mut b = bond(BondMode::BalanceTlb);
+        b.options = Some(BondOptions {
+            transmit_hash_policy: None,
+            lacp_rate: Some(LacpRate::Fast),
+            ad_select: None,
+        });
+        assert!(b.validate().is_err());
+
+        b.options = Some(BondOptions {
+            transmit_hash_policy: None,
+            lacp_rate: None,
+            ad_select: Some(AdSelect::Bandwidth),
+        });
+        assert!(b.validate().is_err());
+    }
+
+    #[test]
+    fn parses_8023ad_mode_string() {
+        let mode: BondMode = serde_plain::from_str("802.3ad").unwrap();
+        assert_eq!(mode, BondMode::Ieee8023ad);
+    }
+
+    #[test]
+    fn rejects_invalid_mode_string() {
+        let result = serde_plain::from_str::<BondMode>("8023ad");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn lacp_rate_and_ad_select_allowed_for_8023ad() {
+        let mut b = bond(BondMode::Ieee8023ad);
+        b.options = Some(BondOptions {
+            transmit_hash_policy: None,
+            lacp_rate: Some(LacpRate::Fast),
+            ad_select: Some(AdSelect::Bandwidth),
+        });
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn mtu_auto_is_accepted() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.mtu = Some(Mtu::Auto);
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn mtu_below_ipv6_minimum_is_rejected() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.mtu = Some(Mtu::Value(1279));
+        assert!(b.validate().is_err());
+    }
+
+    #[test]
+    fn mtu_at_ipv6_minimum_is_accepted() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.mtu = Some(Mtu::Value(1280));
+        assert!(b.validate().is_ok());
+    }
+
+    #[test]
+    fn mtu_value_is_emitted_in_netdev_config() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.mtu = Some(Mtu::Value(9000));
+        let config = b.to_netdev_config(&InterfaceName::try_from("bond0").unwrap());
+        assert!(config.contains("MTUBytes=9000"));
+    }
+
+    #[test]
+    fn mtu_auto_emits_no_mtubytes_line() {
+        let mut b = bond(BondMode::ActiveBackup);
+        b.mtu = Some(Mtu::Auto);
+        let config = b.to_netdev_config(&InterfaceName::try_from("bond0").unwrap());
+        assert!(!config.contains("MTUBytes"));
+    }
+
+    fn arp_monitoring(
+        targets: Vec<IpAddr>,
+        all_targets: Option<ArpAllTargets>,
+    ) -> ArpMonitoringConfig {
+        ArpMonitoringConfig {
+            interval: 100,
+            validate: ArpValidate::Active,
+            targets,
+            all_targets,
+        }
+    }
+
+    #[test]
+    fn duplicate_arp_targets_are_rejected() {
+        let config = arp_monitoring(
+            vec![
+                "192.168.1.1".parse().unwrap(),
+                "192.168.1.1".parse().unwrap(),
+            ],
+            None,
+        );
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn mixed_family_arp_targets_are_rejected() {
+        let config = arp_monitoring(
+            vec!["192.168.1.1".parse().unwrap(), "::1".parse().unwrap()],
+            None,
+        );
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn single_family_arp_targets_are_accepted() {
+        let config = arp_monitoring(
+            vec![
+                "192.168.1.1".parse().unwrap(),
+                "192.168.1.2".parse().unwrap(),
+            ],
+            None,
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn arp_all_targets_is_emitted_in_netdev_config() {
+        let config = arp_monitoring(
+            vec!["192.168.1.1".parse().unwrap()],
+            Some(ArpAllTargets::All),
+        );
+        assert!(config.to_netdev_config().contains("ARPAllTargets=all"));
+    }
+
+    #[test]
+    fn arp_all_targets_absent_emits_no_line() {
+        let config = arp_monitoring(vec!["192.168.1.1".parse().unwrap()], None);
+        assert!(!config.to_netdev_config().contains("ARPAllTargets"));
+    }
+}
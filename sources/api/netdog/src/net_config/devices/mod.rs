@@ -0,0 +1,33 @@
+pub(crate) mod bonding;
+pub(crate) mod vlan;
+pub(crate) mod wireguard;
+
+pub(crate) use bonding::NetBondV1;
+pub(crate) use vlan::NetVlanV1;
+pub(crate) use wireguard::NetWireGuardV1;
+
+/// Implements `net_config::Addressing` for a device struct so `validate_addressing` can check
+/// its dhcp4/dhcp6/static4/static6 fields without each device repeating the same logic.
+macro_rules! generate_addressing_validation {
+    ($ty:ty) => {
+        impl crate::net_config::Addressing for $ty {
+            fn dhcp4(&self) -> &Option<crate::net_config::Dhcp4ConfigV1> {
+                &self.dhcp4
+            }
+
+            fn dhcp6(&self) -> &Option<crate::net_config::Dhcp6ConfigV1> {
+                &self.dhcp6
+            }
+
+            fn static4(&self) -> &Option<crate::net_config::StaticConfigV1> {
+                &self.static4
+            }
+
+            fn static6(&self) -> &Option<crate::net_config::StaticConfigV1> {
+                &self.static6
+            }
+        }
+    };
+}
+
+pub(crate) use generate_addressing_validation;
@@ -0,0 +1,168 @@
+use super::validate_addressing;
+use super::{error, Dhcp4ConfigV1, Dhcp6ConfigV1, Result, RouteV1, StaticConfigV1, Validate};
+use crate::interface_name::InterfaceName;
+use crate::net_config::devices::generate_addressing_validation;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use snafu::ensure;
+use std::collections::HashSet;
+
+const MIN_VLAN_ID: u16 = 1;
+const MAX_VLAN_ID: u16 = 4094;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(remote = "Self")]
+pub(crate) struct NetVlanV1 {
+    pub(crate) dhcp4: Option<Dhcp4ConfigV1>,
+    pub(crate) dhcp6: Option<Dhcp6ConfigV1>,
+    pub(crate) static4: Option<StaticConfigV1>,
+    pub(crate) static6: Option<StaticConfigV1>,
+    #[serde(rename = "route")]
+    pub(crate) routes: Option<Vec<RouteV1>>,
+    kind: String,
+    pub(crate) id: u16,
+    pub(crate) parent: InterfaceName,
+}
+
+impl<'de> Deserialize<'de> for NetVlanV1 {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let this = Self::deserialize(deserializer)?;
+        if this.kind.to_lowercase().as_str() != "vlan" {
+            return Err(D::Error::custom(format!(
+                "kind of '{}' does not match 'vlan'",
+                this.kind.as_str()
+            )));
+        }
+
+        Ok(this)
+    }
+}
+
+generate_addressing_validation!(&NetVlanV1);
+
+impl Validate for NetVlanV1 {
+    fn validate(&self) -> Result<()> {
+        validate_addressing(self)?;
+
+        ensure!(
+            (MIN_VLAN_ID..=MAX_VLAN_ID).contains(&self.id),
+            error::InvalidNetConfigSnafu {
+                reason: format!(
+                    "vlan id must be between {} and {}",
+                    MIN_VLAN_ID, MAX_VLAN_ID
+                )
+            }
+        );
+
+        // Whether `parent` names another vlan, and whether it names an interface that exists at
+        // all, can only be known once every device in net.toml has been parsed; `validate_vlans`
+        // enforces those once the full set of devices is available.
+
+        Ok(())
+    }
+}
+
+impl NetVlanV1 {
+    /// Render this device's `.netdev` unit, the file systemd-networkd reads to create the VLAN
+    /// sub-interface. This unit does not name `parent`: systemd-networkd takes that link from a
+    /// `VLAN=<name>` line in the parent's own `.network` file, which this function does not write.
+    pub(crate) fn to_netdev_config(&self, name: &InterfaceName) -> String {
+        format!(
+            "[NetDev]\nName={}\nKind=vlan\n\n[VLAN]\nId={}\n",
+            name, self.id
+        )
+    }
+}
+
+/// Cross-device validation for VLANs, run once every device in net.toml has been parsed (unlike
+/// `NetVlanV1::validate`, which only sees its own fields): every VLAN's `parent` must name
+/// another configured interface, and that interface must not itself be a VLAN, since the kernel
+/// does not support stacking 802.1Q tags on a VLAN sub-interface.
+pub(crate) fn validate_vlans<'a>(
+    vlans: impl Iterator<Item = (&'a InterfaceName, &'a NetVlanV1)> + Clone,
+    other_interfaces: &HashSet<InterfaceName>,
+) -> Result<()> {
+    let vlan_names: HashSet<&InterfaceName> = vlans.clone().map(|(name, _)| name).collect();
+    for (name, vlan) in vlans {
+        ensure!(
+            other_interfaces.contains(&vlan.parent) || vlan_names.contains(&vlan.parent),
+            error::InvalidNetConfigSnafu {
+                reason: format!(
+                    "vlan '{}' has parent '{}' which is not a configured interface",
+                    name, vlan.parent
+                )
+            }
+        );
+        ensure!(
+            !vlan_names.contains(&vlan.parent),
+            error::InvalidNetConfigSnafu {
+                reason: format!(
+                    "vlan '{}' has parent '{}' which is itself a vlan; vlans cannot stack",
+                    name, vlan.parent
+                )
+            }
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn vlan(id: u16, parent: &str) -> NetVlanV1 {
+        NetVlanV1 {
+            dhcp4: None,
+            dhcp6: None,
+            static4: None,
+            static6: None,
+            routes: None,
+            kind: "vlan".to_string(),
+            id,
+            parent: InterfaceName::try_from(parent).unwrap(),
+        }
+    }
+
+    #[test]
+    fn id_out_of_range_is_rejected() {
+        assert!(vlan(0, "bond0").validate().is_err());
+        assert!(vlan(4095, "bond0").validate().is_err());
+    }
+
+    #[test]
+    fn id_in_range_is_accepted() {
+        assert!(vlan(1, "bond0").validate().is_ok());
+        assert!(vlan(4094, "bond0").validate().is_ok());
+    }
+
+    #[test]
+    fn parent_must_be_a_configured_interface() {
+        let management = vlan(100, "bond0");
+        let vlans = vec![(InterfaceName::try_from("vlan100").unwrap(), management)];
+        let known = HashSet::new();
+        assert!(validate_vlans(vlans.iter().map(|(n, v)| (n, v)), &known).is_err());
+
+        let mut known = HashSet::new();
+        known.insert(InterfaceName::try_from("bond0").unwrap());
+        assert!(validate_vlans(vlans.iter().map(|(n, v)| (n, v)), &known).is_ok());
+    }
+
+    #[test]
+    fn parent_cannot_itself_be_a_vlan() {
+        let storage = vlan(200, "vlan100");
+        let management = vlan(100, "bond0");
+        let vlans = vec![
+            (InterfaceName::try_from("vlan200").unwrap(), storage),
+            (InterfaceName::try_from("vlan100").unwrap(), management),
+        ];
+        let mut known = HashSet::new();
+        known.insert(InterfaceName::try_from("bond0").unwrap());
+        assert!(validate_vlans(vlans.iter().map(|(n, v)| (n, v)), &known).is_err());
+    }
+}
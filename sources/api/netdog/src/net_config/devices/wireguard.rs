@@ -0,0 +1,269 @@
+use super::validate_addressing;
+use super::{error, Dhcp4ConfigV1, Dhcp6ConfigV1, Result, RouteV1, StaticConfigV1, Validate};
+use crate::interface_name::InterfaceName;
+use crate::net_config::devices::generate_addressing_validation;
+use ipnet::IpNet;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use snafu::ensure;
+use std::collections::HashSet;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[serde(remote = "Self")]
+pub(crate) struct NetWireGuardV1 {
+    pub(crate) dhcp4: Option<Dhcp4ConfigV1>,
+    pub(crate) dhcp6: Option<Dhcp6ConfigV1>,
+    pub(crate) static4: Option<StaticConfigV1>,
+    pub(crate) static6: Option<StaticConfigV1>,
+    #[serde(rename = "route")]
+    pub(crate) routes: Option<Vec<RouteV1>>,
+    kind: String,
+    #[serde(rename = "private-key")]
+    pub(crate) private_key: Option<String>,
+    #[serde(rename = "private-key-file")]
+    pub(crate) private_key_file: Option<String>,
+    #[serde(rename = "listen-port")]
+    pub(crate) listen_port: Option<u16>,
+    #[serde(rename = "min-keepalive-sec")]
+    pub(crate) min_keepalive: Option<u16>,
+    #[serde(rename = "max-keepalive-sec")]
+    pub(crate) max_keepalive: Option<u16>,
+    pub(crate) peers: Vec<WireGuardPeer>,
+}
+
+impl<'de> Deserialize<'de> for NetWireGuardV1 {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let this = Self::deserialize(deserializer)?;
+        if this.kind.to_lowercase().as_str() != "wireguard" {
+            return Err(D::Error::custom(format!(
+                "kind of '{}' does not match 'wireguard'",
+                this.kind.as_str()
+            )));
+        }
+
+        Ok(this)
+    }
+}
+
+generate_addressing_validation!(&NetWireGuardV1);
+
+impl Validate for NetWireGuardV1 {
+    fn validate(&self) -> Result<()> {
+        validate_addressing(self)?;
+
+        ensure!(
+            self.private_key.is_some() != self.private_key_file.is_some(),
+            error::InvalidNetConfigSnafu {
+                reason: "exactly one of private-key or private-key-file must be specified"
+            }
+        );
+
+        if let (Some(min), Some(max)) = (self.min_keepalive, self.max_keepalive) {
+            ensure!(
+                min <= max,
+                error::InvalidNetConfigSnafu {
+                    reason: "min-keepalive-sec must be less than or equal to max-keepalive-sec"
+                }
+            );
+        }
+
+        ensure!(
+            !self.peers.is_empty(),
+            error::InvalidNetConfigSnafu {
+                reason: "wireguard devices must have 1 or more peers specified"
+            }
+        );
+
+        let mut seen_public_keys = HashSet::new();
+        for peer in &self.peers {
+            ensure!(
+                seen_public_keys.insert(peer.public_key.as_str()),
+                error::InvalidNetConfigSnafu {
+                    reason: "peers must not share a public-key"
+                }
+            );
+
+            ensure!(
+                !peer.allowed_ips.is_empty(),
+                error::InvalidNetConfigSnafu {
+                    reason: "wireguard peers must have 1 or more allowed-ips specified"
+                }
+            );
+
+            if let Some(keepalive) = peer.persistent_keepalive {
+                if let Some(min) = self.min_keepalive {
+                    ensure!(
+                        keepalive >= min,
+                        error::InvalidNetConfigSnafu {
+                            reason: "peer persistent-keepalive-sec is below min-keepalive-sec"
+                        }
+                    );
+                }
+                if let Some(max) = self.max_keepalive {
+                    ensure!(
+                        keepalive <= max,
+                        error::InvalidNetConfigSnafu {
+                            reason: "peer persistent-keepalive-sec is above max-keepalive-sec"
+                        }
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl NetWireGuardV1 {
+    /// Render this device's `.netdev` unit, the file systemd-networkd reads to create the
+    /// WireGuard interface and its peers so a bond or static config can sit on top of the
+    /// resulting tunnel.
+    pub(crate) fn to_netdev_config(&self, name: &InterfaceName) -> String {
+        let mut netdev = format!("[NetDev]\nName={}\nKind=wireguard\n\n[WireGuard]\n", name);
+        if let Some(private_key) = &self.private_key {
+            netdev.push_str(&format!("PrivateKey={}\n", private_key));
+        }
+        if let Some(private_key_file) = &self.private_key_file {
+            netdev.push_str(&format!("PrivateKeyFile={}\n", private_key_file));
+        }
+        if let Some(listen_port) = self.listen_port {
+            netdev.push_str(&format!("ListenPort={}\n", listen_port));
+        }
+
+        for peer in &self.peers {
+            netdev.push_str(&peer.to_netdev_config());
+        }
+
+        netdev
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct WireGuardPeer {
+    #[serde(rename = "public-key")]
+    pub(crate) public_key: String,
+    #[serde(rename = "preshared-key")]
+    pub(crate) preshared_key: Option<String>,
+    #[serde(rename = "allowed-ips")]
+    pub(crate) allowed_ips: Vec<IpNet>,
+    pub(crate) endpoint: Option<String>,
+    #[serde(rename = "persistent-keepalive-sec")]
+    pub(crate) persistent_keepalive: Option<u16>,
+}
+
+impl WireGuardPeer {
+    /// Render this peer as a `.netdev` `[WireGuardPeer]` section.
+    fn to_netdev_config(&self) -> String {
+        let allowed_ips = self
+            .allowed_ips
+            .iter()
+            .map(IpNet::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let mut section = format!(
+            "\n[WireGuardPeer]\nPublicKey={}\nAllowedIPs={}\n",
+            self.public_key, allowed_ips
+        );
+        if let Some(preshared_key) = &self.preshared_key {
+            section.push_str(&format!("PresharedKey={}\n", preshared_key));
+        }
+        if let Some(endpoint) = &self.endpoint {
+            section.push_str(&format!("Endpoint={}\n", endpoint));
+        }
+        if let Some(keepalive) = self.persistent_keepalive {
+            section.push_str(&format!("PersistentKeepalive={}\n", keepalive));
+        }
+
+        section
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    fn peer(public_key: &str) -> WireGuardPeer {
+        WireGuardPeer {
+            public_key: public_key.to_string(),
+            preshared_key: None,
+            allowed_ips: vec!["10.0.0.0/24".parse().unwrap()],
+            endpoint: None,
+            persistent_keepalive: None,
+        }
+    }
+
+    fn wireguard() -> NetWireGuardV1 {
+        NetWireGuardV1 {
+            dhcp4: None,
+            dhcp6: None,
+            static4: None,
+            static6: None,
+            routes: None,
+            kind: "wireguard".to_string(),
+            private_key: Some("private-key".to_string()),
+            private_key_file: None,
+            listen_port: None,
+            min_keepalive: None,
+            max_keepalive: None,
+            peers: vec![peer("peer-a")],
+        }
+    }
+
+    #[test]
+    fn requires_exactly_one_of_private_key_or_file() {
+        let mut w = wireguard();
+        w.private_key = None;
+        assert!(w.validate().is_err());
+
+        w.private_key = Some("private-key".to_string());
+        w.private_key_file = Some("/etc/wireguard/key".to_string());
+        assert!(w.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_empty_allowed_ips() {
+        let mut w = wireguard();
+        w.peers[0].allowed_ips = Vec::new();
+        assert!(w.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_duplicate_peer_public_keys() {
+        let mut w = wireguard();
+        w.peers.push(peer("peer-a"));
+        assert!(w.validate().is_err());
+    }
+
+    #[test]
+    fn rejects_keepalive_outside_bounds() {
+        let mut w = wireguard();
+        w.min_keepalive = Some(15);
+        w.max_keepalive = Some(60);
+        w.peers[0].persistent_keepalive = Some(5);
+        assert!(w.validate().is_err());
+
+        w.peers[0].persistent_keepalive = Some(120);
+        assert!(w.validate().is_err());
+
+        w.peers[0].persistent_keepalive = Some(30);
+        assert!(w.validate().is_ok());
+    }
+
+    #[test]
+    fn netdev_config_includes_peer_sections() {
+        let mut w = wireguard();
+        w.peers[0].persistent_keepalive = Some(25);
+        let config = w.to_netdev_config(&InterfaceName::try_from("wg0").unwrap());
+        assert!(config.contains("[WireGuard]"));
+        assert!(config.contains("PrivateKey=private-key"));
+        assert!(config.contains("[WireGuardPeer]"));
+        assert!(config.contains("PublicKey=peer-a"));
+        assert!(config.contains("PersistentKeepalive=25"));
+    }
+}
@@ -0,0 +1,10 @@
+use snafu::Snafu;
+
+pub(crate) type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub(crate) enum Error {
+    #[snafu(display("Invalid network configuration: {}", reason))]
+    InvalidNetConfig { reason: String },
+}
@@ -0,0 +1,113 @@
+//! Parsing and validation for the network devices a user can configure in `net.toml`, which pins
+//! settings the network backend would otherwise decide on its own (interface bonding, VLANs,
+//! WireGuard tunnels, and the DHCP/static addressing that sits on top of them).
+
+pub(crate) mod devices;
+pub(crate) mod error;
+
+use crate::interface_name::InterfaceName;
+use devices::vlan::{validate_vlans, NetVlanV1};
+use devices::{NetBondV1, NetWireGuardV1};
+use serde::Deserialize;
+use snafu::ensure;
+use std::collections::{HashMap, HashSet};
+use std::net::IpAddr;
+
+pub(crate) use error::Result;
+
+pub(crate) trait Validate {
+    fn validate(&self) -> Result<()>;
+}
+
+/// The fields every addressable device (bond, VLAN, WireGuard tunnel, ...) carries so it can be
+/// handed a DHCP or static IP configuration; `generate_addressing_validation!` implements this
+/// for a device struct so `validate_addressing` can check it generically.
+pub(crate) trait Addressing {
+    fn dhcp4(&self) -> &Option<Dhcp4ConfigV1>;
+    fn dhcp6(&self) -> &Option<Dhcp6ConfigV1>;
+    fn static4(&self) -> &Option<StaticConfigV1>;
+    fn static6(&self) -> &Option<StaticConfigV1>;
+}
+
+pub(crate) fn validate_addressing<T: Addressing>(device: T) -> Result<()> {
+    ensure!(
+        device.dhcp4().is_none() || device.static4().is_none(),
+        error::InvalidNetConfigSnafu {
+            reason: "dhcp4 and static4 cannot both be configured for the same interface"
+        }
+    );
+    ensure!(
+        device.dhcp6().is_none() || device.static6().is_none(),
+        error::InvalidNetConfigSnafu {
+            reason: "dhcp6 and static6 cannot both be configured for the same interface"
+        }
+    );
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Dhcp4ConfigV1 {
+    pub(crate) enabled: bool,
+    #[serde(rename = "route-metric")]
+    pub(crate) route_metric: Option<u32>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Dhcp6ConfigV1 {
+    pub(crate) enabled: bool,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct StaticConfigV1 {
+    pub(crate) addresses: Vec<IpAddr>,
+    pub(crate) gateway: Option<IpAddr>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RouteV1 {
+    pub(crate) to: IpAddr,
+    pub(crate) via: Option<IpAddr>,
+}
+
+/// The full set of devices a user can configure in `net.toml`.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct NetConfig {
+    #[serde(default)]
+    pub(crate) bonds: HashMap<InterfaceName, NetBondV1>,
+    #[serde(default)]
+    pub(crate) vlans: HashMap<InterfaceName, NetVlanV1>,
+    #[serde(default)]
+    pub(crate) wireguard_devices: HashMap<InterfaceName, NetWireGuardV1>,
+}
+
+impl Validate for NetConfig {
+    fn validate(&self) -> Result<()> {
+        for bond in self.bonds.values() {
+            bond.validate()?;
+        }
+        for vlan in self.vlans.values() {
+            vlan.validate()?;
+        }
+        for wireguard_device in self.wireguard_devices.values() {
+            wireguard_device.validate()?;
+        }
+
+        // Vlans may parent off a bond, a WireGuard tunnel, or (eventually) a physical interface,
+        // but not another vlan; this can only be checked once every device is in hand.
+        let other_interfaces: HashSet<InterfaceName> = self
+            .bonds
+            .keys()
+            .chain(self.wireguard_devices.keys())
+            .cloned()
+            .collect();
+        validate_vlans(self.vlans.iter(), &other_interfaces)?;
+
+        Ok(())
+    }
+}